@@ -5,12 +5,18 @@ use crate::common::DepositDataTree;
 use crate::upgrade::{
     upgrade_to_altair, upgrade_to_bellatrix, upgrade_to_capella, upgrade_to_deneb,
 };
+use bls::{verify_signature_sets, SignatureSet};
+use merkle_proof::MerkleTree;
 use safe_arith::{ArithError, SafeArith};
+use std::borrow::Cow;
 use tree_hash::TreeHash;
-use types::DEPOSIT_TREE_DEPTH;
+use types::{DEPOSIT_TREE_DEPTH, VALIDATOR_REGISTRY_LIMIT};
 use types::*;
 
 /// Initialize a `BeaconState` from genesis data.
+///
+/// Dispatches to `initialize_beacon_state_at_fork` for whichever fork's epoch equals
+/// `T::genesis_epoch()` (or `ForkName::Base` if none do).
 pub fn initialize_beacon_state_from_eth1<T: EthSpec>(
     eth1_block_hash: Hash256,
     eth1_timestamp: u64,
@@ -18,105 +24,272 @@ pub fn initialize_beacon_state_from_eth1<T: EthSpec>(
     execution_payload_header: Option<ExecutionPayloadHeader<T>>,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, BlockProcessingError> {
-    let genesis_time = eth2_genesis_time(eth1_timestamp, spec)?;
-    let eth1_data = Eth1Data {
-        // Temporary deposit root
-        deposit_root: Hash256::zero(),
-        deposit_count: deposits.len() as u64,
-        block_hash: eth1_block_hash,
-    };
-    let mut state = BeaconState::new(genesis_time, eth1_data, spec);
-
-    // Seed RANDAO with Eth1 entropy
-    state.fill_randao_mixes_with(eth1_block_hash);
-
-    let mut deposit_tree = DepositDataTree::create(&[], 0, DEPOSIT_TREE_DEPTH);
-
-    for deposit in deposits.iter() {
-        deposit_tree
-            .push_leaf(deposit.data.tree_hash_root())
-            .map_err(BlockProcessingError::MerkleTreeError)?;
-        state.eth1_data_mut().deposit_root = deposit_tree.root();
-        process_deposit(&mut state, deposit, spec, true)?;
+    let fork = genesis_fork_name::<T>(spec);
+    initialize_beacon_state_at_fork(
+        fork,
+        eth1_block_hash,
+        eth1_timestamp,
+        deposits,
+        execution_payload_header,
+        spec,
+    )
+}
+
+/// Initialize a `BeaconState` already upgraded to `fork`.
+///
+/// Builds a Phase0 state from the deposits and then runs the upgrades on the path to `fork`,
+/// oldest first (see `GenesisBuilder::finalize_at_fork`); the `fork` variant of the state is not
+/// constructed directly. `state.fork.current_version` and `state.fork.previous_version` both
+/// end up equal to `fork`'s genesis version. Sync committees are computed once, by the Altair
+/// upgrade, and the caches are built once, at the end.
+pub fn initialize_beacon_state_at_fork<T: EthSpec>(
+    fork: ForkName,
+    eth1_block_hash: Hash256,
+    eth1_timestamp: u64,
+    deposits: Vec<Deposit>,
+    execution_payload_header: Option<ExecutionPayloadHeader<T>>,
+    spec: &ChainSpec,
+) -> Result<BeaconState<T>, BlockProcessingError> {
+    let mut builder = GenesisBuilder::new(eth1_block_hash, eth1_timestamp, spec)?;
+    builder.push_deposits(deposits.iter(), spec)?;
+    builder.finalize_at_fork(fork, execution_payload_header, spec)
+}
+
+/// Determine which fork (if any) is configured to activate at the genesis epoch for `T`,
+/// checking from the latest fork backwards since later fork epochs are only meaningful once
+/// every earlier one has also activated at or before genesis.
+fn genesis_fork_name<T: EthSpec>(spec: &ChainSpec) -> ForkName {
+    let at_genesis = |fork_epoch: Option<Epoch>| fork_epoch == Some(T::genesis_epoch());
+
+    if at_genesis(spec.deneb_fork_epoch) {
+        ForkName::Deneb
+    } else if at_genesis(spec.capella_fork_epoch) {
+        ForkName::Capella
+    } else if at_genesis(spec.bellatrix_fork_epoch) {
+        ForkName::Bellatrix
+    } else if at_genesis(spec.altair_fork_epoch) {
+        ForkName::Altair
+    } else {
+        ForkName::Base
     }
+}
+
+/// A streaming, resumable builder for constructing a genesis `BeaconState` from an
+/// incremental stream of deposits.
+///
+/// Unlike `initialize_beacon_state_from_eth1`, which requires the full deposit set to be
+/// resident in memory, `GenesisBuilder` lets deposits be pushed one (or a batch) at a time as
+/// they are read from disk or an eth1 log stream. Callers can snapshot `state()`/
+/// `deposit_tree()` between pushes and later resume with `from_parts`, without re-reading
+/// deposits that were already applied.
+pub struct GenesisBuilder<T: EthSpec> {
+    state: BeaconState<T>,
+    deposit_tree: DepositDataTree,
+    deposit_count: u64,
+}
 
-    process_activations(&mut state, spec)?;
+impl<T: EthSpec> GenesisBuilder<T> {
+    /// Start building a genesis state from the given eth1 block hash/timestamp, before any
+    /// deposits have been applied.
+    pub fn new(
+        eth1_block_hash: Hash256,
+        eth1_timestamp: u64,
+        spec: &ChainSpec,
+    ) -> Result<Self, BlockProcessingError> {
+        let genesis_time = eth2_genesis_time(eth1_timestamp, spec)?;
+        let eth1_data = Eth1Data {
+            // Temporary deposit root
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: eth1_block_hash,
+        };
+        let mut state = BeaconState::new(genesis_time, eth1_data, spec);
 
-    // To support testnets with Altair enabled from genesis, perform a possible state upgrade here.
-    // This must happen *after* deposits and activations are processed or the calculation of sync
-    // committees during the upgrade will fail. It's a bit cheeky to do this instead of having
-    // separate Altair genesis initialization logic, but it turns out that our
-    // use of `BeaconBlock::empty` in `BeaconState::new` is sufficient to correctly initialise
-    // the `latest_block_header` as per:
-    // https://github.com/ethereum/eth2.0-specs/pull/2323
-    if spec
-        .altair_fork_epoch
-        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
-    {
-        upgrade_to_altair(&mut state, spec)?;
+        // Seed RANDAO with Eth1 entropy
+        state.fill_randao_mixes_with(eth1_block_hash);
 
-        state.fork_mut().previous_version = spec.altair_fork_version;
+        Ok(Self {
+            state,
+            deposit_tree: DepositDataTree::create(&[], 0, DEPOSIT_TREE_DEPTH),
+            deposit_count: 0,
+        })
     }
 
-    // Similarly, perform an upgrade to the merge if configured from genesis.
-    if spec
-        .bellatrix_fork_epoch
-        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
-    {
-        // this will set state.latest_execution_payload_header = ExecutionPayloadHeaderMerge::default()
-        upgrade_to_bellatrix(&mut state, spec)?;
+    /// Number of deposits applied so far.
+    pub fn deposit_count(&self) -> u64 {
+        self.deposit_count
+    }
 
-        // Remove intermediate Altair fork from `state.fork`.
-        state.fork_mut().previous_version = spec.bellatrix_fork_version;
+    /// The in-progress genesis state, as built up from the deposits applied so far.
+    pub fn state(&self) -> &BeaconState<T> {
+        &self.state
+    }
+
+    /// The in-progress incremental deposit merkle tree, as built up from the deposits applied
+    /// so far.
+    pub fn deposit_tree(&self) -> &DepositDataTree {
+        &self.deposit_tree
+    }
 
-        // Override latest execution payload header.
-        // See https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/bellatrix/beacon-chain.md#testing
-        if let Some(ExecutionPayloadHeader::Merge(ref header)) = execution_payload_header {
-            *state.latest_execution_payload_header_merge_mut()? = header.clone();
+    /// Resume building from a `state`/`deposit_tree`/`deposit_count` snapshotted earlier (e.g.
+    /// via `state()`/`deposit_tree()`/`deposit_count()` and persisted to disk), without
+    /// re-reading the deposits that produced them.
+    ///
+    /// The three parts must come from the same snapshot: `push_deposit`/`push_deposits` always
+    /// write `deposit_count` back into `state.eth1_data().deposit_count`, so a `deposit_count`
+    /// that disagrees with it means the caller resumed from a torn or stale snapshot.
+    pub fn from_parts(
+        state: BeaconState<T>,
+        deposit_tree: DepositDataTree,
+        deposit_count: u64,
+    ) -> Self {
+        debug_assert_eq!(
+            state.eth1_data().deposit_count,
+            deposit_count,
+            "resumed deposit_count disagrees with the snapshotted state's eth1_data.deposit_count"
+        );
+        Self {
+            state,
+            deposit_tree,
+            deposit_count,
         }
     }
 
-    // Upgrade to capella if configured from genesis
-    if spec
-        .capella_fork_epoch
-        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
-    {
-        upgrade_to_capella(&mut state, spec)?;
+    /// Apply a single deposit, first updating `eth1_data.deposit_root` and
+    /// `eth1_data.deposit_count` incrementally to include it.
+    pub fn push_deposit(
+        &mut self,
+        deposit: &Deposit,
+        spec: &ChainSpec,
+    ) -> Result<(), BlockProcessingError> {
+        self.deposit_tree
+            .push_leaf(deposit.data.tree_hash_root())
+            .map_err(BlockProcessingError::MerkleTreeError)?;
+        self.deposit_count.safe_add_assign(1)?;
+        self.state.eth1_data_mut().deposit_root = self.deposit_tree.root();
+        self.state.eth1_data_mut().deposit_count = self.deposit_count;
+        process_deposit(&mut self.state, deposit, spec, true)
+    }
+
+    /// Apply a sequence of deposits, batch-verifying their signatures up front exactly as
+    /// `initialize_beacon_state_from_eth1` does, and otherwise pushing each in order as
+    /// `push_deposit` would.
+    pub fn push_deposits<'a>(
+        &mut self,
+        deposits: impl Iterator<Item = &'a Deposit> + Clone,
+        spec: &ChainSpec,
+    ) -> Result<(), BlockProcessingError> {
+        let invalid_signatures = verify_deposit_signatures(deposits.clone(), spec);
 
-        // Remove intermediate Bellatrix fork from `state.fork`.
-        state.fork_mut().previous_version = spec.capella_fork_version;
+        for (i, deposit) in deposits.enumerate() {
+            self.deposit_tree
+                .push_leaf(deposit.data.tree_hash_root())
+                .map_err(BlockProcessingError::MerkleTreeError)?;
+            self.deposit_count.safe_add_assign(1)?;
+            self.state.eth1_data_mut().deposit_root = self.deposit_tree.root();
+            self.state.eth1_data_mut().deposit_count = self.deposit_count;
 
-        // Override latest execution payload header.
-        // See https://github.com/ethereum/consensus-specs/blob/dev/specs/capella/beacon-chain.md#testing
-        if let Some(ExecutionPayloadHeader::Capella(ref header)) = execution_payload_header {
-            *state.latest_execution_payload_header_capella_mut()? = header.clone();
+            // Deposits already known-invalid from the batch check above still need
+            // `process_deposit` to re-verify their signature, so it rejects them (and skips
+            // activation) exactly as the unbatched path would; deposits that passed the batch
+            // check skip the redundant per-deposit verification.
+            let reverify_to_reject = invalid_signatures.binary_search(&i).is_ok();
+            process_deposit(&mut self.state, deposit, spec, reverify_to_reject)?;
         }
-    }
 
-    // Upgrade to deneb if configured from genesis
-    if spec
-        .deneb_fork_epoch
-        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
-    {
-        upgrade_to_deneb(&mut state, spec)?;
+        Ok(())
+    }
 
-        // Remove intermediate Capella fork from `state.fork`.
-        state.fork_mut().previous_version = spec.deneb_fork_version;
+    /// Finish building the genesis state already upgraded to `fork`: process activations,
+    /// run the upgrades on the path to `fork` in order (oldest first, rather than
+    /// independently probing every fork's epoch against genesis), and build the caches needed
+    /// to start the chain. Sync committees are computed once, by the Altair upgrade.
+    ///
+    /// This must happen *after* deposits and activations are processed or the calculation of
+    /// sync committees during the upgrade will fail. It's a bit cheeky to do this instead of
+    /// having separate Altair genesis initialization logic, but it turns out that our use of
+    /// `BeaconBlock::empty` in `BeaconState::new` is sufficient to correctly initialise the
+    /// `latest_block_header` as per:
+    /// https://github.com/ethereum/eth2.0-specs/pull/2323
+    pub fn finalize_at_fork(
+        mut self,
+        fork: ForkName,
+        execution_payload_header: Option<ExecutionPayloadHeader<T>>,
+        spec: &ChainSpec,
+    ) -> Result<BeaconState<T>, BlockProcessingError> {
+        process_activations(&mut self.state, spec)?;
 
-        // Override latest execution payload header.
-        // See https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/beacon-chain.md#testing
-        if let Some(ExecutionPayloadHeader::Deneb(header)) = execution_payload_header {
-            *state.latest_execution_payload_header_deneb_mut()? = header;
+        for upgrade in GENESIS_UPGRADE_ORDER.into_iter().take_while(|f| *f <= fork) {
+            match upgrade {
+                ForkName::Altair => upgrade_to_altair(&mut self.state, spec)?,
+                ForkName::Bellatrix => upgrade_to_bellatrix(&mut self.state, spec)?,
+                ForkName::Capella => upgrade_to_capella(&mut self.state, spec)?,
+                ForkName::Deneb => upgrade_to_deneb(&mut self.state, spec)?,
+                ForkName::Base => {}
+            }
         }
+
+        // Per spec, genesis states have no real "previous" fork: both versions are the
+        // target fork's genesis version.
+        let fork_version = genesis_fork_version(fork, spec);
+        self.state.fork_mut().previous_version = fork_version;
+        self.state.fork_mut().current_version = fork_version;
+
+        install_execution_payload_header(&mut self.state, execution_payload_header)?;
+
+        // Now that we have our validators, initialize the caches (including the committees)
+        self.state.build_caches(spec)?;
+
+        // Set genesis validators root for domain separation and chain versioning
+        *self.state.genesis_validators_root_mut() =
+            self.state.update_validators_tree_hash_cache()?;
+
+        Ok(self.state)
     }
+}
 
-    // Now that we have our validators, initialize the caches (including the committees)
-    state.build_caches(spec)?;
+/// Forks that may be activated from genesis, oldest to newest. `ForkName` orders by age, so
+/// `take_while(|f| *f <= fork)` below selects exactly the upgrades needed to reach `fork`.
+const GENESIS_UPGRADE_ORDER: [ForkName; 4] = [
+    ForkName::Altair,
+    ForkName::Bellatrix,
+    ForkName::Capella,
+    ForkName::Deneb,
+];
 
-    // Set genesis validators root for domain separation and chain versioning
-    *state.genesis_validators_root_mut() = state.update_validators_tree_hash_cache()?;
+/// The genesis fork version associated with `fork`.
+fn genesis_fork_version(fork: ForkName, spec: &ChainSpec) -> [u8; 4] {
+    match fork {
+        ForkName::Base => spec.genesis_fork_version,
+        ForkName::Altair => spec.altair_fork_version,
+        ForkName::Bellatrix => spec.bellatrix_fork_version,
+        ForkName::Capella => spec.capella_fork_version,
+        ForkName::Deneb => spec.deneb_fork_version,
+    }
+}
 
-    Ok(state)
+/// Install `execution_payload_header` into whichever of `state`'s execution-payload-header
+/// fields matches its variant, mirroring the overrides the sequential upgrade chain used to
+/// apply one fork at a time.
+///
+/// See https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/bellatrix/beacon-chain.md#testing
+fn install_execution_payload_header<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    execution_payload_header: Option<ExecutionPayloadHeader<T>>,
+) -> Result<(), BlockProcessingError> {
+    match execution_payload_header {
+        Some(ExecutionPayloadHeader::Merge(header)) => {
+            *state.latest_execution_payload_header_merge_mut()? = header;
+        }
+        Some(ExecutionPayloadHeader::Capella(header)) => {
+            *state.latest_execution_payload_header_capella_mut()? = header;
+        }
+        Some(ExecutionPayloadHeader::Deneb(header)) => {
+            *state.latest_execution_payload_header_deneb_mut()? = header;
+        }
+        None => {}
+    }
+    Ok(())
 }
 
 /// Determine whether a candidate genesis state is suitable for starting the chain.
@@ -130,6 +303,12 @@ pub fn is_valid_genesis_state<T: EthSpec>(state: &BeaconState<T>, spec: &ChainSp
 }
 
 /// Activate genesis validators, if their balance is acceptable.
+///
+/// Goes via `split_validator`/`join_validator` below and only ever mutates the
+/// `MutableValidatorData` half (`effective_balance`, `activation_eligibility_epoch`,
+/// `activation_epoch`) — `ImmutableValidatorData` (`pubkey`, `withdrawal_credentials`) passes
+/// through untouched — so a `BeaconState` whose validator storage adopted that split would
+/// only need to expose the mutable slice here, not the full `Validator` list.
 pub fn process_activations<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
@@ -140,18 +319,369 @@ pub fn process_activations<T: EthSpec>(
             .get(index)
             .copied()
             .ok_or(Error::BalancesOutOfBounds(index))?;
-        validator.effective_balance = std::cmp::min(
+        let (immutable, mut mutable) = split_validator(validator);
+        mutable.effective_balance = std::cmp::min(
             balance.safe_sub(balance.safe_rem(spec.effective_balance_increment)?)?,
             spec.max_effective_balance,
         );
-        if validator.effective_balance == spec.max_effective_balance {
-            validator.activation_eligibility_epoch = T::genesis_epoch();
-            validator.activation_epoch = T::genesis_epoch();
+        if mutable.effective_balance == spec.max_effective_balance {
+            mutable.activation_eligibility_epoch = T::genesis_epoch();
+            mutable.activation_epoch = T::genesis_epoch();
         }
+        *validator = join_validator(&immutable, &mutable);
     }
     Ok(())
 }
 
+/// The identity fields of a `Validator` that never change after genesis.
+///
+/// Borrowed from the "NoImmutableValidators" layout used by other clients' DB layers: at
+/// scale, `Validator` records dominate genesis state memory even though these two fields are
+/// set once at genesis and never touched again by `process_activations` or later epoch
+/// processing. `process_activations` below round-trips every validator through
+/// `split_validator`/`join_validator` and only ever writes to the `MutableValidatorData` half.
+///
+/// NB: `BeaconState`'s own validator storage (in the `types` crate) still stores the
+/// canonical `Vec<Validator>`, so this doesn't yet save memory — adopting the split for
+/// storage too would mean changing that layout, which is out of scope for this patch. This
+/// type, `MutableValidatorData`, and the conversions between them and `Validator` are the
+/// building blocks for doing so, and `process_activations` is already written against the
+/// mutable-only projection they'd expose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImmutableValidatorData {
+    pub pubkey: PublicKeyBytes,
+    pub withdrawal_credentials: Hash256,
+}
+
+/// The fields of a `Validator` that genesis activation and later epoch processing mutate.
+/// See `ImmutableValidatorData` for the fields this is split from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MutableValidatorData {
+    pub effective_balance: u64,
+    pub slashed: bool,
+    pub activation_eligibility_epoch: Epoch,
+    pub activation_epoch: Epoch,
+    pub exit_epoch: Epoch,
+    pub withdrawable_epoch: Epoch,
+}
+
+impl ImmutableValidatorData {
+    fn from_validator(validator: &Validator) -> Self {
+        Self {
+            pubkey: validator.pubkey,
+            withdrawal_credentials: validator.withdrawal_credentials,
+        }
+    }
+}
+
+impl MutableValidatorData {
+    fn from_validator(validator: &Validator) -> Self {
+        Self {
+            effective_balance: validator.effective_balance,
+            slashed: validator.slashed,
+            activation_eligibility_epoch: validator.activation_eligibility_epoch,
+            activation_epoch: validator.activation_epoch,
+            exit_epoch: validator.exit_epoch,
+            withdrawable_epoch: validator.withdrawable_epoch,
+        }
+    }
+}
+
+/// Split a canonical `Validator` into its immutable and mutable parts, e.g. so genesis can
+/// populate the immutable table once from deposits and only touch the mutable slice during
+/// `process_activations`.
+pub fn split_validator(validator: &Validator) -> (ImmutableValidatorData, MutableValidatorData) {
+    (
+        ImmutableValidatorData::from_validator(validator),
+        MutableValidatorData::from_validator(validator),
+    )
+}
+
+/// Recombine a split immutable/mutable pair back into the canonical `Validator`, so that e.g.
+/// `update_validators_tree_hash_cache` can still produce the correct
+/// `genesis_validators_root` from whichever storage representation is in use.
+pub fn join_validator(
+    immutable: &ImmutableValidatorData,
+    mutable: &MutableValidatorData,
+) -> Validator {
+    Validator {
+        pubkey: immutable.pubkey,
+        withdrawal_credentials: immutable.withdrawal_credentials,
+        effective_balance: mutable.effective_balance,
+        slashed: mutable.slashed,
+        activation_eligibility_epoch: mutable.activation_eligibility_epoch,
+        activation_epoch: mutable.activation_epoch,
+        exit_epoch: mutable.exit_epoch,
+        withdrawable_epoch: mutable.withdrawable_epoch,
+    }
+}
+
+/// A merkle-tree generalized index, per the consensus spec: the root is `1`, and a node's
+/// children are `2g` and `2g + 1`. A leaf's generalized index lets a verifier check it against
+/// a single root, without knowing (or re-deriving) the rest of the tree.
+pub type GeneralizedIndex = u64;
+
+/// One step of a path through an SSZ container or list/vector, for generalized-index
+/// computation. Mirrors the two shapes the spec's `get_generalized_index(ssz_class, *path)`
+/// descends through.
+#[derive(Debug, Clone, Copy)]
+pub enum GeneralizedIndexStep {
+    /// Field `index` of a container with `num_fields` fields in total. SSZ merkleizes a
+    /// container over the next power of two of its field count, so the field's generalized
+    /// index is offset accordingly within its parent `g`.
+    ContainerField { index: u64, num_fields: u64 },
+    /// Element `index` of a `List`/`Vector` whose elements occupy `chunk_depth` levels below
+    /// the chunks' subtree root. A `List` additionally mixes in a length node as the sibling
+    /// of that subtree, so its chunks hang off generalized index `2g` rather than `g` itself;
+    /// a `Vector` has no length mixin and uses `g` directly.
+    ListElement {
+        index: u64,
+        chunk_depth: u32,
+        is_list: bool,
+    },
+}
+
+/// Port of the consensus spec's `get_generalized_index(ssz_class, *path)`: fold a path of
+/// [`GeneralizedIndexStep`]s, starting from the root (generalized index `1`), into the
+/// generalized index of the leaf they describe.
+pub fn get_generalized_index(path: &[GeneralizedIndexStep]) -> GeneralizedIndex {
+    path.iter().fold(1u64, |g, step| match *step {
+        GeneralizedIndexStep::ContainerField { index, num_fields } => {
+            g * num_fields.next_power_of_two() + index
+        }
+        GeneralizedIndexStep::ListElement {
+            index,
+            chunk_depth,
+            is_list,
+        } => {
+            let chunks_root = if is_list { g * 2 } else { g };
+            (chunks_root << chunk_depth) + index
+        }
+    })
+}
+
+/// Extension trait adding generalized-index inclusion proofs to `DepositDataTree`, so that
+/// light clients and deposit-processing tooling can verify a deposit leaf is committed under
+/// `eth1_data.deposit_root` without re-deriving the whole tree.
+pub trait ProveDeposit {
+    /// Returns the leaf, the merkle branch from that leaf up to the tree root, and the
+    /// generalized index of `deposit_index` within the deposit list's merkle tree.
+    fn prove_deposit(
+        &self,
+        deposit_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>, GeneralizedIndex), Error>;
+}
+
+impl ProveDeposit for DepositDataTree {
+    fn prove_deposit(
+        &self,
+        deposit_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>, GeneralizedIndex), Error> {
+        // `DepositDataTree::generate_proof` already mixes in the list's length as the final
+        // element of the branch (it knows its own depth from `create`), so the branch is
+        // `DEPOSIT_TREE_DEPTH + 1` hashes long -- one level deeper than the plain chunk tree.
+        let (leaf, branch) = self.generate_proof(deposit_index);
+        let generalized_index = get_generalized_index(&[GeneralizedIndexStep::ListElement {
+            index: deposit_index as u64,
+            chunk_depth: DEPOSIT_TREE_DEPTH as u32,
+            is_list: true,
+        }]);
+        Ok((leaf, branch, generalized_index))
+    }
+}
+
+/// The top-level fields of a Phase0 (`ForkName::Base`) `BeaconState`, in SSZ container field
+/// order. `GenesisBuilder` always starts from a Base-fork state (`BeaconState::new`) and runs
+/// `process_activations` before any upgrade, so this is the layout genesis's own field proofs
+/// need; later forks insert/replace fields and would need their own variant of this list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeaconStateField {
+    GenesisTime,
+    GenesisValidatorsRoot,
+    Slot,
+    Fork,
+    LatestBlockHeader,
+    BlockRoots,
+    StateRoots,
+    HistoricalRoots,
+    Eth1Data,
+    Eth1DataVotes,
+    Eth1DepositIndex,
+    Validators,
+    Balances,
+    RandaoMixes,
+    Slashings,
+    PreviousEpochAttestations,
+    CurrentEpochAttestations,
+    JustificationBits,
+    PreviousJustifiedCheckpoint,
+    CurrentJustifiedCheckpoint,
+    FinalizedCheckpoint,
+}
+
+impl BeaconStateField {
+    /// Total number of top-level fields in a Phase0 `BeaconState` container.
+    const NUM_FIELDS: u64 = 21;
+
+    /// This field's index among `NUM_FIELDS`, in declaration (= SSZ container) order.
+    fn field_index(self) -> u64 {
+        self as u64
+    }
+
+    /// The tree-hash root of `state`'s value for this field: the leaf that
+    /// `get_generalized_index`'s `ContainerField` step for this field points at.
+    fn root<T: EthSpec>(self, state: &BeaconState<T>) -> Result<Hash256, Error> {
+        Ok(match self {
+            BeaconStateField::GenesisTime => state.genesis_time().tree_hash_root(),
+            BeaconStateField::GenesisValidatorsRoot => {
+                state.genesis_validators_root().tree_hash_root()
+            }
+            BeaconStateField::Slot => state.slot().tree_hash_root(),
+            BeaconStateField::Fork => state.fork().tree_hash_root(),
+            BeaconStateField::LatestBlockHeader => state.latest_block_header().tree_hash_root(),
+            BeaconStateField::BlockRoots => state.block_roots().tree_hash_root(),
+            BeaconStateField::StateRoots => state.state_roots().tree_hash_root(),
+            BeaconStateField::HistoricalRoots => state.historical_roots().tree_hash_root(),
+            BeaconStateField::Eth1Data => state.eth1_data().tree_hash_root(),
+            BeaconStateField::Eth1DataVotes => state.eth1_data_votes().tree_hash_root(),
+            BeaconStateField::Eth1DepositIndex => state.eth1_deposit_index().tree_hash_root(),
+            BeaconStateField::Validators => state.validators().tree_hash_root(),
+            BeaconStateField::Balances => state.balances().tree_hash_root(),
+            BeaconStateField::RandaoMixes => state.randao_mixes().tree_hash_root(),
+            BeaconStateField::Slashings => state.slashings().tree_hash_root(),
+            // Phase0-only: later forks drop these in favour of participation flags, and their
+            // accessors are fallible for exactly that reason.
+            BeaconStateField::PreviousEpochAttestations => {
+                state.previous_epoch_attestations()?.tree_hash_root()
+            }
+            BeaconStateField::CurrentEpochAttestations => {
+                state.current_epoch_attestations()?.tree_hash_root()
+            }
+            BeaconStateField::JustificationBits => state.justification_bits().tree_hash_root(),
+            BeaconStateField::PreviousJustifiedCheckpoint => {
+                state.previous_justified_checkpoint().tree_hash_root()
+            }
+            BeaconStateField::CurrentJustifiedCheckpoint => {
+                state.current_justified_checkpoint().tree_hash_root()
+            }
+            BeaconStateField::FinalizedCheckpoint => state.finalized_checkpoint().tree_hash_root(),
+        })
+    }
+}
+
+/// All `BeaconStateField`s, in declaration (= SSZ container) order.
+const ALL_BEACON_STATE_FIELDS: [BeaconStateField; BeaconStateField::NUM_FIELDS as usize] = [
+    BeaconStateField::GenesisTime,
+    BeaconStateField::GenesisValidatorsRoot,
+    BeaconStateField::Slot,
+    BeaconStateField::Fork,
+    BeaconStateField::LatestBlockHeader,
+    BeaconStateField::BlockRoots,
+    BeaconStateField::StateRoots,
+    BeaconStateField::HistoricalRoots,
+    BeaconStateField::Eth1Data,
+    BeaconStateField::Eth1DataVotes,
+    BeaconStateField::Eth1DepositIndex,
+    BeaconStateField::Validators,
+    BeaconStateField::Balances,
+    BeaconStateField::RandaoMixes,
+    BeaconStateField::Slashings,
+    BeaconStateField::PreviousEpochAttestations,
+    BeaconStateField::CurrentEpochAttestations,
+    BeaconStateField::JustificationBits,
+    BeaconStateField::PreviousJustifiedCheckpoint,
+    BeaconStateField::CurrentJustifiedCheckpoint,
+    BeaconStateField::FinalizedCheckpoint,
+];
+
+/// `ALL_BEACON_STATE_FIELDS` and `BeaconStateField::root` only know the Phase0/Base container
+/// layout, so proofs built from them are only valid for a `BeaconState::Base`. Later forks
+/// replace `previous_epoch_attestations`/`current_epoch_attestations` with participation-flag
+/// fields and append further fields, which would silently shift every generalized index below.
+fn ensure_base_state<T: EthSpec>(state: &BeaconState<T>) -> Result<(), Error> {
+    match state {
+        BeaconState::Base(_) => Ok(()),
+        _ => Err(Error::IncorrectStateVariant),
+    }
+}
+
+/// Prove that the top-level field `field` of `state` is committed under `state`'s own root.
+///
+/// A `BeaconState` container merkleizes by hashing each field to one leaf, padding to the next
+/// power of two of the field count (`32`, for `BeaconStateField::NUM_FIELDS` fields), with no
+/// length mix-in (containers, unlike lists, don't have a dynamic length).
+fn prove_beacon_state_container_field<T: EthSpec>(
+    state: &BeaconState<T>,
+    field: BeaconStateField,
+) -> Result<(Hash256, Vec<Hash256>, GeneralizedIndex), Error> {
+    ensure_base_state(state)?;
+    let leaves = ALL_BEACON_STATE_FIELDS
+        .iter()
+        .map(|f| f.root(state))
+        .collect::<Result<Vec<Hash256>, Error>>()?;
+    let depth = BeaconStateField::NUM_FIELDS.next_power_of_two().trailing_zeros() as usize;
+    let tree = MerkleTree::create(&leaves, depth);
+    let (leaf, branch) = tree
+        .generate_proof(field.field_index() as usize, depth)
+        .map_err(Error::MerkleTreeError)?;
+    let generalized_index = get_generalized_index(&[GeneralizedIndexStep::ContainerField {
+        index: field.field_index(),
+        num_fields: BeaconStateField::NUM_FIELDS,
+    }]);
+    Ok((leaf, branch, generalized_index))
+}
+
+/// Prove that `state.validators()[validator_index]` is committed under `state`'s own root.
+///
+/// `validators` is an SSZ `List<Validator, VALIDATOR_REGISTRY_LIMIT>`, which merkleizes over
+/// the list's declared *capacity* (not its current length) and then mixes in the length as the
+/// sibling of that capacity subtree's root -- exactly as `update_validators_tree_hash_cache`
+/// computes it. The proof composes two `GeneralizedIndexStep`s: `Validators`' own position as a
+/// `BeaconState` container field (`prove_beacon_state_container_field`), then
+/// `validator_index`'s position within that field's list, so the branch and generalized index
+/// verify against the whole-state root rather than just the validators list's own root.
+pub fn prove_state_field<T: EthSpec>(
+    state: &BeaconState<T>,
+    validator_index: usize,
+) -> Result<(Hash256, Vec<Hash256>, GeneralizedIndex), Error> {
+    ensure_base_state(state)?;
+    let validators = state.validators();
+    let leaves: Vec<Hash256> = validators
+        .iter()
+        .map(|validator| validator.tree_hash_root())
+        .collect();
+    let capacity_depth = (VALIDATOR_REGISTRY_LIMIT as u64)
+        .next_power_of_two()
+        .trailing_zeros() as usize;
+    let tree = MerkleTree::create(&leaves, capacity_depth);
+    let (leaf, mut branch) = tree
+        .generate_proof(validator_index, capacity_depth)
+        .map_err(Error::MerkleTreeError)?;
+
+    // Mix in the list's length: the capacity subtree's root is the left sibling of the length
+    // leaf, so the length leaf is the next sibling on the branch up to the `Validators` field's
+    // own root.
+    branch.push((validators.len() as u64).tree_hash_root());
+
+    // Continue the branch from the `Validators` field's own root up to the whole-state root.
+    let (_, container_branch, _) =
+        prove_beacon_state_container_field(state, BeaconStateField::Validators)?;
+    branch.extend(container_branch);
+
+    let generalized_index = get_generalized_index(&[
+        GeneralizedIndexStep::ContainerField {
+            index: BeaconStateField::Validators.field_index(),
+            num_fields: BeaconStateField::NUM_FIELDS,
+        },
+        GeneralizedIndexStep::ListElement {
+            index: validator_index as u64,
+            chunk_depth: capacity_depth as u32,
+            is_list: true,
+        },
+    ]);
+    Ok((leaf, branch, generalized_index))
+}
+
 /// Returns the `state.genesis_time` for the corresponding `eth1_timestamp`.
 ///
 /// Does _not_ ensure that the time is greater than `MIN_GENESIS_TIME`.
@@ -160,3 +690,224 @@ pub fn process_activations<T: EthSpec>(
 pub fn eth2_genesis_time(eth1_timestamp: u64, spec: &ChainSpec) -> Result<u64, ArithError> {
     eth1_timestamp.safe_add(spec.genesis_delay)
 }
+
+/// Decompress a deposit's public key and signature and compute its signing root, returning
+/// `None` if either fails to decompress (in which case the deposit is necessarily invalid).
+fn decompress_deposit_signature(
+    deposit: &Deposit,
+    spec: &ChainSpec,
+) -> Option<(PublicKey, AggregateSignature, Hash256)> {
+    let pubkey = deposit.data.pubkey.decompress().ok()?;
+    let signature = deposit.data.signature.decompress().ok()?;
+    let domain = spec.get_deposit_domain();
+    let signing_root = deposit.data.as_deposit_message().signing_root(domain);
+    Some((pubkey, AggregateSignature::from(&signature), signing_root))
+}
+
+/// Batch-verify the BLS signatures of `deposits` in a single aggregate (random-coefficient)
+/// operation, following the state-transition approach of verifying many signatures at once
+/// rather than one at a time. Returns the indices (in iteration order) of deposits whose
+/// signature is invalid or malformed.
+///
+/// Only falls back to verifying deposits one at a time when the aggregate check fails, in
+/// order to locate the offending deposit(s).
+fn verify_deposit_signatures<'a>(
+    deposits: impl Iterator<Item = &'a Deposit>,
+    spec: &ChainSpec,
+) -> Vec<usize> {
+    let decompressed: Vec<Option<(PublicKey, AggregateSignature, Hash256)>> = deposits
+        .map(|deposit| decompress_deposit_signature(deposit, spec))
+        .collect();
+
+    // Deposits that failed to decompress are unconditionally invalid.
+    let mut invalid: Vec<usize> = decompressed
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| entry.is_none().then_some(i))
+        .collect();
+
+    let signature_sets: Vec<SignatureSet> = decompressed
+        .iter()
+        .filter_map(|entry| entry.as_ref())
+        .map(|(pubkey, signature, signing_root)| {
+            SignatureSet::single_pubkey(signature, Cow::Borrowed(pubkey), *signing_root)
+        })
+        .collect();
+
+    if !signature_sets.is_empty() && !verify_signature_sets(signature_sets.iter()) {
+        // The aggregate check failed: fall back to verifying each deposit individually to
+        // find the offending one(s). This is the rare, slow path.
+        for (i, entry) in decompressed.iter().enumerate() {
+            if let Some((pubkey, signature, signing_root)) = entry {
+                if !SignatureSet::single_pubkey(signature, Cow::Borrowed(pubkey), *signing_root)
+                    .is_valid()
+                {
+                    invalid.push(i);
+                }
+            }
+        }
+    }
+
+    invalid.sort_unstable();
+    invalid.dedup();
+    invalid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle_proof::verify_merkle_proof;
+    use types::test_utils::generate_deterministic_keypair;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    /// Build a genesis-valid, correctly-signed deposit for validator `index`.
+    fn make_deposit(index: u64, spec: &ChainSpec) -> Deposit {
+        let keypair = generate_deterministic_keypair(index as usize);
+        let mut data = DepositData {
+            pubkey: keypair.pk.compress().into(),
+            withdrawal_credentials: Hash256::from_low_u64_le(index),
+            amount: spec.max_effective_balance,
+            signature: Signature::empty().into(),
+        };
+        let domain = spec.get_deposit_domain();
+        let signing_root = data.as_deposit_message().signing_root(domain);
+        data.signature = keypair.sk.sign(signing_root).into();
+        Deposit {
+            proof: FixedVector::default(),
+            data,
+        }
+    }
+
+    /// Verify `(leaf, branch, generalized_index)` reconstructs `root`, without assuming
+    /// anything about how the generalized index was composed (single-step or multi-step).
+    fn assert_proof_verifies(
+        leaf: Hash256,
+        branch: &[Hash256],
+        generalized_index: GeneralizedIndex,
+        root: Hash256,
+    ) {
+        let depth = branch.len();
+        let index = (generalized_index - (1u64 << depth)) as usize;
+        assert!(
+            verify_merkle_proof(leaf, branch, depth, index, root),
+            "proof with generalized index {generalized_index} at depth {depth} did not verify"
+        );
+    }
+
+    #[test]
+    fn split_then_join_validator_round_trips() {
+        let keypair = generate_deterministic_keypair(0);
+        let validator = Validator {
+            pubkey: keypair.pk.compress().into(),
+            withdrawal_credentials: Hash256::from_low_u64_le(7),
+            effective_balance: 32_000_000_000,
+            slashed: false,
+            activation_eligibility_epoch: Epoch::new(1),
+            activation_epoch: Epoch::new(2),
+            exit_epoch: Epoch::new(3),
+            withdrawable_epoch: Epoch::new(4),
+        };
+
+        let (immutable, mutable) = split_validator(&validator);
+
+        assert_eq!(join_validator(&immutable, &mutable), validator);
+    }
+
+    #[test]
+    fn prove_deposit_verifies_against_deposit_root() {
+        let spec = ChainSpec::minimal();
+        let mut builder =
+            GenesisBuilder::<E>::new(Hash256::zero(), 0, &spec).expect("builder starts");
+        let deposits: Vec<Deposit> = (0..4).map(|i| make_deposit(i, &spec)).collect();
+        builder
+            .push_deposits(deposits.iter(), &spec)
+            .expect("deposits apply");
+
+        let deposit_tree = builder.deposit_tree();
+        for (i, deposit) in deposits.iter().enumerate() {
+            let (leaf, branch, generalized_index) =
+                deposit_tree.prove_deposit(i).expect("proof generates");
+            assert_eq!(leaf, deposit.data.tree_hash_root());
+            assert_proof_verifies(leaf, &branch, generalized_index, deposit_tree.root());
+        }
+    }
+
+    #[test]
+    fn prove_state_field_verifies_against_state_root() {
+        let spec = ChainSpec::minimal();
+        let mut builder =
+            GenesisBuilder::<E>::new(Hash256::zero(), 0, &spec).expect("builder starts");
+        let deposits: Vec<Deposit> = (0..4).map(|i| make_deposit(i, &spec)).collect();
+        builder
+            .push_deposits(deposits.iter(), &spec)
+            .expect("deposits apply");
+        let state = builder
+            .finalize_at_fork(ForkName::Base, None, &spec)
+            .expect("genesis state finalizes");
+
+        let root = state.tree_hash_root();
+        for i in 0..state.validators().len() {
+            let (leaf, branch, generalized_index) =
+                prove_state_field(&state, i).expect("proof generates");
+            assert_eq!(leaf, state.validators()[i].tree_hash_root());
+            assert_proof_verifies(leaf, &branch, generalized_index, root);
+        }
+    }
+
+    /// A mix of signature-valid and signature-invalid deposits must land the batched
+    /// `push_deposits` path on the same state as pushing each deposit individually through
+    /// `push_deposit` (which always re-verifies), since that's the semantics
+    /// `reverify_to_reject` in `push_deposits` is supposed to preserve.
+    #[test]
+    fn push_deposits_matches_per_deposit_baseline_with_invalid_signature() {
+        let spec = ChainSpec::minimal();
+        let mut deposits: Vec<Deposit> = (0..4).map(|i| make_deposit(i, &spec)).collect();
+        // Corrupt one deposit's signature, so the batch contains both valid and invalid
+        // entries.
+        deposits[2].data.signature = Signature::empty().into();
+
+        let mut batched =
+            GenesisBuilder::<E>::new(Hash256::zero(), 0, &spec).expect("builder starts");
+        batched
+            .push_deposits(deposits.iter(), &spec)
+            .expect("batched deposits apply");
+
+        let mut baseline =
+            GenesisBuilder::<E>::new(Hash256::zero(), 0, &spec).expect("builder starts");
+        for deposit in &deposits {
+            baseline
+                .push_deposit(deposit, &spec)
+                .expect("baseline deposit applies");
+        }
+
+        assert_eq!(
+            batched.state().validators().to_vec(),
+            baseline.state().validators().to_vec()
+        );
+        assert_eq!(
+            batched.state().balances().to_vec(),
+            baseline.state().balances().to_vec()
+        );
+    }
+
+    #[test]
+    fn prove_state_field_rejects_non_base_state() {
+        let spec = ChainSpec::minimal();
+        let mut builder =
+            GenesisBuilder::<E>::new(Hash256::zero(), 0, &spec).expect("builder starts");
+        let deposits: Vec<Deposit> = (0..4).map(|i| make_deposit(i, &spec)).collect();
+        builder
+            .push_deposits(deposits.iter(), &spec)
+            .expect("deposits apply");
+        let state = builder
+            .finalize_at_fork(ForkName::Altair, None, &spec)
+            .expect("genesis state finalizes");
+
+        assert_eq!(
+            prove_state_field(&state, 0),
+            Err(Error::IncorrectStateVariant)
+        );
+    }
+}